@@ -0,0 +1,168 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module with the Prometheus metrics collected while harvesting short positions.
+
+use crate::domain::{DbError, Regulator};
+use prometheus::{Encoder, GaugeVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use sqlx::PgPool;
+
+/// Prometheus metrics derived from [IbexShortFeeder][crate::feeders::IbexShortFeeder].
+///
+/// # Description
+///
+/// This `struct` owns a private [Registry] and exposes it rendered in the Prometheus text
+/// exposition format through [Metrics::render]. The weight and active-position gauges reflect
+/// the data base at the time of the scrape ([Metrics::refresh_position_gauges]), while the
+/// companies-processed gauge and the per-regulator scrape counters are pushed by the feeder as
+/// it runs a harvest.
+pub struct Metrics {
+    registry: Registry,
+    /// Aggregate short `weight` per ticker.
+    short_weight: GaugeVec,
+    /// Count of active short positions per ticker.
+    active_positions: IntGaugeVec,
+    /// Number of companies processed during the last harvest run.
+    companies_processed: IntGauge,
+    /// Scrape attempts per regulator, labelled by outcome ("success"/"failure").
+    scrape_total: IntCounterVec,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Class constructor. Registers every metric against a fresh, private [Registry].
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let short_weight = GaugeVec::new(
+            Opts::new(
+                "ibex35_short_weight_percent",
+                "Aggregate short weight percent currently held against a ticker",
+            ),
+            &["ticker"],
+        )
+        .expect("invalid metric definition");
+
+        let active_positions = IntGaugeVec::new(
+            Opts::new(
+                "ibex35_short_active_positions",
+                "Number of active short positions held against a ticker",
+            ),
+            &["ticker"],
+        )
+        .expect("invalid metric definition");
+
+        let companies_processed = IntGauge::new(
+            "ibex35_companies_processed",
+            "Number of companies processed during the last harvest run",
+        )
+        .expect("invalid metric definition");
+
+        let scrape_total = IntCounterVec::new(
+            Opts::new(
+                "ibex35_scrape_total",
+                "Scrape attempts per market regulator",
+            ),
+            &["regulator", "outcome"],
+        )
+        .expect("invalid metric definition");
+
+        registry
+            .register(Box::new(short_weight.clone()))
+            .expect("failed to register metric");
+        registry
+            .register(Box::new(active_positions.clone()))
+            .expect("failed to register metric");
+        registry
+            .register(Box::new(companies_processed.clone()))
+            .expect("failed to register metric");
+        registry
+            .register(Box::new(scrape_total.clone()))
+            .expect("failed to register metric");
+
+        Metrics {
+            registry,
+            short_weight,
+            active_positions,
+            companies_processed,
+            scrape_total,
+        }
+    }
+
+    /// Records the number of companies listed for the harvest run that is starting.
+    pub fn set_companies_processed(&self, count: i64) {
+        self.companies_processed.set(count);
+    }
+
+    /// Records the outcome of a scrape attempt against a given regulator.
+    pub fn record_scrape(&self, regulator: Regulator, success: bool) {
+        let outcome = if success { "success" } else { "failure" };
+        self.scrape_total
+            .with_label_values(&[&regulator.to_string(), outcome])
+            .inc();
+    }
+
+    /// Re-reads the current aggregate weight and active position count per ticker from the DB.
+    ///
+    /// # Description
+    ///
+    /// Tickers that are no longer shorted would otherwise keep reporting their last known value
+    /// forever, so both gauge vectors are reset before being repopulated.
+    pub async fn refresh_position_gauges(&self, pool: &PgPool) -> Result<(), DbError> {
+        struct TickerAggregate {
+            ticker: Option<String>,
+            total_weight: Option<f64>,
+            active_count: Option<i64>,
+        }
+
+        let aggregates = sqlx::query_as!(
+            TickerAggregate,
+            r#"
+            SELECT ticker, SUM(weight)::float8 as total_weight, COUNT(*) as active_count
+            FROM alive_positions INNER JOIN ibex35_short_historic on alive_positions.id = ibex35_short_historic.id
+            GROUP BY ticker
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+        self.short_weight.reset();
+        self.active_positions.reset();
+
+        for aggregate in aggregates {
+            let ticker = match aggregate.ticker {
+                Some(t) => t,
+                None => continue,
+            };
+
+            self.short_weight
+                .with_label_values(&[&ticker])
+                .set(aggregate.total_weight.unwrap_or(0.0));
+            self.active_positions
+                .with_label_values(&[&ticker])
+                .set(aggregate.active_count.unwrap_or(0));
+        }
+
+        Ok(())
+    }
+
+    /// Renders every registered metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&metric_families, &mut buffer)
+            .expect("failed to encode the metrics");
+
+        String::from_utf8(buffer).expect("metrics output is not valid utf-8")
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module with the read-only HTTP admin API: a `/metrics` Prometheus endpoint plus JSON
+//! endpoints for consulting the harvested data without touching QuestDB directly.
+
+use crate::admin::Metrics;
+use crate::domain::{AliveShortPositions, DbError, ShortPosition};
+use crate::feeders::ShortPositionBd;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::Utc;
+use sqlx::types::Decimal;
+use sqlx::PgPool;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+#[derive(Clone)]
+struct AppState {
+    pool: PgPool,
+    metrics: Arc<Metrics>,
+}
+
+/// Read-only HTTP server exposing the harvested data and Prometheus metrics.
+///
+/// # Description
+///
+/// This `struct` serves a `/metrics` endpoint reporting gauges and counters derived from the
+/// harvest ([Metrics]), alongside a `/positions` endpoint listing the tickers with active short
+/// positions and a `/positions/{ticker}` endpoint returning a ticker's currently active short
+/// positions, both as JSON. None of these endpoints write to the data base;
+/// the server exists purely so that monitoring and external tools can consult the harvested
+/// data without a direct QuestDB connection.
+pub struct AdminServer {
+    pool: PgPool,
+    metrics: Arc<Metrics>,
+}
+
+impl AdminServer {
+    /// Class constructor.
+    pub fn new(pool: PgPool, metrics: Arc<Metrics>) -> Self {
+        AdminServer { pool, metrics }
+    }
+
+    /// Binds to `addr` and serves the admin API until the process is stopped.
+    #[instrument(name = "Serve the admin API", skip(self))]
+    pub async fn serve(self, addr: SocketAddr) -> std::io::Result<()> {
+        let state = AppState {
+            pool: self.pool,
+            metrics: self.metrics,
+        };
+
+        let app = Router::new()
+            .route("/metrics", get(metrics_handler))
+            .route("/positions", get(list_positions))
+            .route("/positions/:ticker", get(ticker_positions))
+            .with_state(state);
+
+        info!("Admin API listening on {addr}");
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, app).await
+    }
+}
+
+async fn metrics_handler(State(state): State<AppState>) -> Response {
+    if let Err(e) = state.metrics.refresh_position_gauges(&state.pool).await {
+        return db_error_response(e);
+    }
+
+    state.metrics.render().into_response()
+}
+
+async fn list_positions(State(state): State<AppState>) -> Response {
+    match tickers_with_positions(&state.pool).await {
+        Ok(tickers) => Json(tickers).into_response(),
+        Err(e) => db_error_response(e),
+    }
+}
+
+async fn ticker_positions(
+    State(state): State<AppState>,
+    Path(ticker): Path<String>,
+) -> Response {
+    match active_positions_for(&state.pool, &ticker).await {
+        Ok(positions) => Json(positions).into_response(),
+        Err(e) => db_error_response(e),
+    }
+}
+
+async fn tickers_with_positions(pool: &PgPool) -> Result<Vec<String>, DbError> {
+    struct TickerRow {
+        ticker: Option<String>,
+    }
+
+    let rows = sqlx::query_as!(
+        TickerRow,
+        r#"
+        SELECT DISTINCT ticker
+        FROM alive_positions INNER JOIN ibex35_short_historic on alive_positions.id = ibex35_short_historic.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+    Ok(rows.into_iter().filter_map(|row| row.ticker).collect())
+}
+
+async fn active_positions_for(pool: &PgPool, ticker: &str) -> Result<AliveShortPositions, DbError> {
+    let rows = sqlx::query_as!(
+        ShortPositionBd,
+        r#"
+        SELECT id, owner, weight, open_date, ticker, mic
+        FROM alive_positions INNER JOIN ibex35_short_historic on alive_positions.id = ibex35_short_historic.id
+        WHERE ibex35_short_historic.ticker = $1
+        "#,
+        ticker,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+    let mut total = Decimal::ZERO;
+    let mut positions = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let position = ShortPosition::try_from(row)?;
+        total += position.weight;
+        positions.push(position);
+    }
+
+    Ok(AliveShortPositions {
+        total,
+        positions,
+        date: Utc::now(),
+        skipped_rows: 0,
+    })
+}
+
+fn db_error_response(e: DbError) -> Response {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(e)).into_response()
+}
@@ -6,29 +6,160 @@
 
 //! Module with definitions for custom error types.
 
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 /// Error types for the CNMV handler.
 #[derive(Error, Debug)]
 pub enum CnmvError {
     /// Error given when the passed company is not recognized by the CNMV' API.
-    #[error("")]
+    #[error("the given company is not recognized by the CNMV")]
     UnknownCompany,
     /// Error from the external API (CNMV).
-    #[error("")]
+    #[error("the CNMV returned an error: {0}")]
     ExternalError(String),
     /// Error for the internal methods.
-    #[error("")]
+    #[error("internal error while handling the CNMV response: {0}")]
     InternalError(String),
     /// CNMV identifies companies using ISIN.
-    #[error("")]
+    #[error("the given company has no ISIN, which the CNMV requires to identify it")]
     MissingIsin,
 }
 
+impl CnmvError {
+    /// Stable, machine-readable code identifying the kind of error, e.g. `cnmv.unknown_company`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CnmvError::UnknownCompany => "cnmv.unknown_company",
+            CnmvError::ExternalError(_) => "cnmv.external_error",
+            CnmvError::InternalError(_) => "cnmv.internal_error",
+            CnmvError::MissingIsin => "cnmv.missing_isin",
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            CnmvError::ExternalError(detail) | CnmvError::InternalError(detail) => Some(detail),
+            CnmvError::UnknownCompany | CnmvError::MissingIsin => None,
+        }
+    }
+}
+
+impl Serialize for CnmvError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_envelope(serializer, "CnmvError", self.code(), &self.to_string(), self.detail())
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum DbError {
-    #[error("missing stock information in the DB")]
+    #[error("missing stock information in the DB: {0}")]
     MissingStockInfo(String),
-    #[error("unknown db error")]
+    #[error("unknown database error: {0}")]
     Unknown(String),
 }
+
+impl DbError {
+    /// Stable, machine-readable code identifying the kind of error, e.g. `db.unknown`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DbError::MissingStockInfo(_) => "db.missing_stock_info",
+            DbError::Unknown(_) => "db.unknown",
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            DbError::MissingStockInfo(detail) | DbError::Unknown(detail) => Some(detail),
+        }
+    }
+}
+
+impl Serialize for DbError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_envelope(serializer, "DbError", self.code(), &self.to_string(), self.detail())
+    }
+}
+
+/// Serializes any of this module's errors as a `{ "code", "message", "detail" }` JSON envelope.
+fn serialize_envelope<S: Serializer>(
+    serializer: S,
+    name: &'static str,
+    code: &str,
+    message: &str,
+    detail: Option<&str>,
+) -> Result<S::Ok, S::Error> {
+    let mut envelope = serializer.serialize_struct(name, 3)?;
+    envelope.serialize_field("code", code)?;
+    envelope.serialize_field("message", message)?;
+    envelope.serialize_field("detail", &detail)?;
+    envelope.end()
+}
+
+/// Error raised while delivering a [ShortPositionEvent][crate::domain::ShortPositionEvent] to a
+/// webhook.
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    /// Every retry attempt was exhausted without a successful delivery.
+    #[error("failed to deliver the event to the webhook: {0}")]
+    DeliveryFailed(String),
+}
+
+/// Error raised while building or refreshing a [MicRegistry][crate::domain::MicRegistry].
+#[derive(Error, Debug)]
+pub enum ReferenceError {
+    /// The MIC CSV could not be downloaded.
+    #[error("failed to fetch the MIC registry: {0}")]
+    Fetch(String),
+    /// The MIC CSV was downloaded (or read from disk) but could not be parsed.
+    #[error("failed to parse the MIC registry: {0}")]
+    Parse(String),
+    /// The on-disk cache of the MIC registry could not be read or written.
+    #[error("failed to access the cached MIC registry: {0}")]
+    Cache(String),
+}
+
+impl ReferenceError {
+    /// Stable, machine-readable code identifying the kind of error, e.g. `reference.fetch_failed`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReferenceError::Fetch(_) => "reference.fetch_failed",
+            ReferenceError::Parse(_) => "reference.parse_failed",
+            ReferenceError::Cache(_) => "reference.cache_failed",
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            ReferenceError::Fetch(detail)
+            | ReferenceError::Parse(detail)
+            | ReferenceError::Cache(detail) => Some(detail),
+        }
+    }
+}
+
+impl Serialize for ReferenceError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_envelope(serializer, "ReferenceError", self.code(), &self.to_string(), self.detail())
+    }
+}
+
+/// Error wrapper returned by any [ShortPositionProvider][crate::ShortPositionProvider], regardless
+/// of the market regulator it talks to.
+///
+/// # Description
+///
+/// [IbexShortFeeder][crate::feeders::IbexShortFeeder] dispatches to one provider implementation
+/// per [Regulator][crate::domain::Regulator], each with its own error type. This `enum` lets the
+/// feeder handle every provider through the same `Result`, without caring which regulator a
+/// company's data came from.
+#[derive(Error, Debug)]
+pub enum DataProviderError {
+    /// Error raised while talking to the CNMV.
+    #[error("CNMV provider error: {0}")]
+    Cnmv(#[from] CnmvError),
+    /// Error raised while reading from or writing to the data base.
+    #[error("database error: {0}")]
+    Db(#[from] DbError),
+}
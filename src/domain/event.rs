@@ -0,0 +1,48 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module with the definition of short-position change events.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+/// State transition detected for a short position against an Ibex35 company.
+///
+/// # Description
+///
+/// [IbexShortFeeder][crate::feeders::IbexShortFeeder] already distinguishes three kinds of
+/// change whenever it compares the positions just harvested against the ones on record: a
+/// brand-new position, a change in the weight of an existing one, or a position that dropped
+/// below the disclosure threshold. This `enum` captures each transition as a typed event,
+/// broadcast over [IbexShortFeeder::subscribe][crate::feeders::IbexShortFeeder::subscribe] so
+/// that alerting or dashboards can react in real time instead of scraping the logs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum ShortPositionEvent {
+    /// A new short position was opened.
+    Opened {
+        owner: String,
+        ticker: String,
+        weight: Decimal,
+        open_date: DateTime<Utc>,
+    },
+    /// An existing short position changed weight.
+    Updated {
+        owner: String,
+        ticker: String,
+        old_weight: Decimal,
+        new_weight: Decimal,
+        open_date: DateTime<Utc>,
+    },
+    /// A short position dropped below the disclosure threshold.
+    Closed {
+        owner: String,
+        ticker: String,
+        weight: Decimal,
+        open_date: DateTime<Utc>,
+    },
+}
@@ -0,0 +1,192 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module with ISO 10383 Market Identifier Code (MIC) reference data.
+//!
+//! [Regulator][crate::domain::Regulator] tells [IbexShortFeeder][crate::feeders::IbexShortFeeder]
+//! which data provider to dispatch a company to, but a regulator oversees many exchanges. The MIC
+//! is the granularity that actually identifies the venue a short position was disclosed on, and is
+//! the standard code the data base can join on once more than one
+//! [ShortDataProvider][crate::ShortDataProvider] is in play.
+
+use crate::domain::ReferenceError;
+use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// URL of the full ISO 10383 MIC CSV published by ISO20022.
+const DEFAULT_MIC_CSV_URL: &str =
+    "https://www.iso20022.org/sites/default/files/ISO10383_MIC/ISO10383_MIC.csv";
+
+/// A single market listed in the ISO 10383 registry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Mic {
+    /// The market's own MIC, e.g. `XMAD` for the Bolsa de Madrid.
+    #[serde(rename = "MIC")]
+    pub mic: String,
+    /// The MIC of the entity that operates this market, e.g. `BMEX` for the group that operates
+    /// `XMAD`. Equal to [Mic::mic] for markets that are themselves an operating MIC.
+    #[serde(rename = "OPERATING MIC")]
+    pub operating_mic: String,
+    /// Human-readable name of the market or institution.
+    #[serde(rename = "MARKET NAME-INSTITUTION DESCRIPTION")]
+    pub market_name: String,
+    /// ISO 3166 country code the market is registered in.
+    #[serde(rename = "ISO COUNTRY CODE (ISO 3166)")]
+    pub country: String,
+}
+
+/// In-memory lookup table of ISO 10383 Market Identifier Codes.
+///
+/// # Description
+///
+/// This `struct` keeps a [Mic] per market, keyed by its own MIC, so that any
+/// [ShortDataProvider][crate::ShortDataProvider] implementation can tag the
+/// [ShortPosition][crate::domain::ShortPosition]s it produces with a standard market code instead
+/// of each provider inventing its own notion of "exchange". The registry starts empty; call
+/// [MicRegistry::refresh] (or [MicRegistry::load_from_file] to avoid a network round-trip) before
+/// relying on [Self::lookup].
+pub struct MicRegistry {
+    markets: HashMap<String, Mic>,
+}
+
+impl Default for MicRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MicRegistry {
+    /// Class constructor. Returns an empty registry.
+    pub fn new() -> Self {
+        MicRegistry {
+            markets: HashMap::new(),
+        }
+    }
+
+    /// Downloads and parses the full MIC CSV published by ISO20022, replacing any previously
+    /// loaded data.
+    #[instrument(name = "Refresh the MIC registry from ISO20022", skip(self))]
+    pub async fn refresh(&mut self) -> Result<(), ReferenceError> {
+        self.refresh_from_url(DEFAULT_MIC_CSV_URL).await
+    }
+
+    /// Same as [MicRegistry::refresh], but against an arbitrary URL. Mainly useful for testing
+    /// against a stable mirror of the CSV.
+    pub async fn refresh_from_url(&mut self, url: &str) -> Result<(), ReferenceError> {
+        let body = reqwest::get(url)
+            .await
+            .map_err(|e| ReferenceError::Fetch(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| ReferenceError::Fetch(e.to_string()))?;
+
+        self.load_csv(body.as_bytes())
+    }
+
+    /// Loads the registry from a MIC CSV cached on disk by a previous [MicRegistry::save_to_file],
+    /// avoiding a network round-trip.
+    pub fn load_from_file(&mut self, path: impl AsRef<Path>) -> Result<(), ReferenceError> {
+        let body = std::fs::read(path).map_err(|e| ReferenceError::Cache(e.to_string()))?;
+        self.load_csv(&body[..])
+    }
+
+    /// Writes every currently loaded [Mic] to `path` as CSV, so a future run can skip the download
+    /// via [MicRegistry::load_from_file].
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), ReferenceError> {
+        let mut writer =
+            csv::Writer::from_path(path).map_err(|e| ReferenceError::Cache(e.to_string()))?;
+
+        for market in self.markets.values() {
+            writer
+                .serialize(market)
+                .map_err(|e| ReferenceError::Cache(e.to_string()))?;
+        }
+
+        writer
+            .flush()
+            .map_err(|e| ReferenceError::Cache(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn load_csv(&mut self, data: &[u8]) -> Result<(), ReferenceError> {
+        let mut reader = ReaderBuilder::new().has_headers(true).from_reader(data);
+        let mut markets = HashMap::new();
+
+        for record in reader.deserialize::<Mic>() {
+            let market = record.map_err(|e| ReferenceError::Parse(e.to_string()))?;
+            markets.insert(market.mic.clone(), market);
+        }
+
+        info!("Loaded {} markets into the MIC registry", markets.len());
+        self.markets = markets;
+
+        Ok(())
+    }
+
+    /// Looks up a market by its MIC.
+    pub fn lookup(&self, mic: &str) -> Option<&Mic> {
+        self.markets.get(mic)
+    }
+
+    /// Number of markets currently loaded.
+    pub fn len(&self) -> usize {
+        self.markets.len()
+    }
+
+    /// Whether the registry has not been populated yet.
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_CSV: &str = "MIC,OPERATING MIC,MARKET NAME-INSTITUTION DESCRIPTION,ISO COUNTRY CODE (ISO 3166)\nXMAD,BMEX,BOLSA DE MADRID,ES\nXLON,XLON,LONDON STOCK EXCHANGE,GB\n";
+
+    #[test]
+    fn load_csv_populates_the_registry() {
+        let mut registry = MicRegistry::new();
+        assert!(registry.is_empty());
+
+        registry.load_csv(SAMPLE_CSV.as_bytes()).unwrap();
+
+        assert_eq!(registry.len(), 2);
+        let madrid = registry.lookup("XMAD").unwrap();
+        assert_eq!(madrid.operating_mic, "BMEX");
+        assert_eq!(madrid.market_name, "BOLSA DE MADRID");
+        assert_eq!(madrid.country, "ES");
+    }
+
+    #[test]
+    fn lookup_returns_none_for_an_unknown_mic() {
+        let mut registry = MicRegistry::new();
+        registry.load_csv(SAMPLE_CSV.as_bytes()).unwrap();
+
+        assert!(registry.lookup("XNYS").is_none());
+    }
+
+    #[test]
+    fn save_and_load_from_file_round_trips() {
+        let mut registry = MicRegistry::new();
+        registry.load_csv(SAMPLE_CSV.as_bytes()).unwrap();
+
+        let path = std::env::temp_dir().join("mic_registry_round_trip_test.csv");
+        registry.save_to_file(&path).unwrap();
+
+        let mut reloaded = MicRegistry::new();
+        reloaded.load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(reloaded.len(), registry.len());
+        assert_eq!(reloaded.lookup("XLON"), registry.lookup("XLON"));
+    }
+}
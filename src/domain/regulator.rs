@@ -0,0 +1,82 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module with the definition of market regulators and ISIN-based resolution.
+
+use std::fmt;
+
+/// Market regulator in charge of publishing short-position notifications for a company.
+///
+/// # Description
+///
+/// Most of the companies listed in the Ibex35 are incorporated in Spain, so their short
+/// positions are published by the CNMV. A handful of companies are incorporated abroad (e.g.
+/// the Netherlands or Germany), and their short positions have to be retrieved from the
+/// regulator of their home country instead. This `enum` identifies which regulator is
+/// responsible for a given company, so that a [ShortPositionProvider][crate::ShortPositionProvider]
+/// can be looked up for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Regulator {
+    /// Comisión Nacional del Mercado de Valores (Spain).
+    Cnmv,
+    /// Bundesanstalt für Finanzdienstleistungsaufsicht (Germany).
+    BaFin,
+    /// Financial Conduct Authority (United Kingdom).
+    Fca,
+    /// Autoriteit Financiële Markten (The Netherlands).
+    Afm,
+}
+
+impl fmt::Display for Regulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Regulator::Cnmv => "CNMV",
+            Regulator::BaFin => "BaFin",
+            Regulator::Fca => "FCA",
+            Regulator::Afm => "AFM",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Regulator {
+    /// Resolves the regulator in charge of a company from the country prefix of its ISIN.
+    ///
+    /// # Description
+    ///
+    /// The first two letters of an ISIN identify the country in which the security was
+    /// registered (ISO 6166), which is used here as a proxy for the market regulator that the
+    /// company has to report its short positions to. Unrecognised or missing prefixes default
+    /// to [Regulator::Cnmv], since the vast majority of the companies handled by this crate are
+    /// Spanish.
+    pub fn from_isin(isin: &str) -> Regulator {
+        match isin.get(0..2) {
+            Some("DE") => Regulator::BaFin,
+            Some("GB") => Regulator::Fca,
+            Some("NL") => Regulator::Afm,
+            _ => Regulator::Cnmv,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_known_prefixes() {
+        assert_eq!(Regulator::from_isin("ES0171996087"), Regulator::Cnmv);
+        assert_eq!(Regulator::from_isin("DE0007236101"), Regulator::BaFin);
+        assert_eq!(Regulator::from_isin("GB0002875804"), Regulator::Fca);
+        assert_eq!(Regulator::from_isin("NL0000235190"), Regulator::Afm);
+    }
+
+    #[test]
+    fn defaults_to_cnmv_for_unknown_prefixes() {
+        assert_eq!(Regulator::from_isin("XX0000000000"), Regulator::Cnmv);
+        assert_eq!(Regulator::from_isin(""), Regulator::Cnmv);
+    }
+}
@@ -10,24 +10,30 @@ use crate::domain::{CnmvError, DataProviderError};
 use chrono::{DateTime, Utc};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use rust_decimal::Decimal;
+use serde::Serialize;
 use std::fmt;
 
 /// Wrapper for a Result enum that might contain [ShortPosition] entries.
 pub type ShortResult = Result<Vec<ShortPosition>, DataProviderError>;
 
 /// Short position entry.
-#[derive(Default, Debug, PartialEq)]
+#[derive(Default, Debug, PartialEq, Serialize)]
 pub struct ShortPosition {
     /// This is the name of the investment fund that owns the short position.
     pub owner: String,
     /// This is a percentage over the company's total capitalization that indicates
     /// the amount of shares sold in short by the owner against the value of the
-    /// company.
-    pub weight: f32,
+    /// company. Kept as an exact [Decimal] rather than a float, since these values are summed
+    /// and compared against regulatory thresholds.
+    pub weight: Decimal,
     /// Date in which the short position was stated.
     pub open_date: DateTime<Utc>,
     /// The ticker of the asset.
     pub ticker: String,
+    /// ISO 10383 Market Identifier Code of the exchange this position was disclosed on. See
+    /// [MicRegistry][crate::domain::MicRegistry] for looking up the market it refers to.
+    pub mic: String,
 }
 
 impl fmt::Display for ShortPosition {
@@ -45,23 +51,31 @@ impl fmt::Display for ShortPosition {
 ///
 /// Short positions are stated once per day, no later than 15:30. Thus a full timestamp
 /// is not really useful. Only the date is kept for the entries.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct AliveShortPositions {
-    /// Summation of all the active [ShortPosition::weight] of the company.
-    pub total: f32,
+    /// Summation of all the active [ShortPosition::weight] of the company. Kept as an exact
+    /// [Decimal] so that aggregating many positions does not drift from rounding error.
+    pub total: Decimal,
     /// Collection of active [ShortPosition] for a company.
     pub positions: Vec<ShortPosition>,
     /// Timestamp of the active positions.
     pub date: DateTime<Utc>,
+    /// Number of rows that could not be parsed out of the source table and were skipped.
+    ///
+    /// A non-zero value alongside an empty or suspiciously small [positions](Self::positions)
+    /// collection is a sign that the source's layout changed and broke parsing, rather than the
+    /// company genuinely having no short positions.
+    pub skipped_rows: usize,
 }
 
 impl AliveShortPositions {
     /// Constructor of the [AliveShortPositions] class.
     pub fn new() -> AliveShortPositions {
         AliveShortPositions {
-            total: 0.0,
+            total: Decimal::ZERO,
             positions: Vec::new(),
             date: Utc::now(),
+            skipped_rows: 0,
         }
     }
 }
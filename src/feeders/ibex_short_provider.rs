@@ -4,15 +4,61 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{web_scrappers::CnmvProvider, DbError, ShortPosition};
+use crate::{
+    admin::Metrics, domain::MicRegistry, web_scrappers::CnmvProvider, DataProviderError, DbError,
+    Regulator, ShortPosition, ShortPositionEvent, ShortPositionProvider, ShortResult,
+};
 use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
 use finance_api::Company;
 use finance_ibex::IbexCompany;
-use sqlx::{prelude::FromRow, types::Uuid, Executor, PgPool};
+use futures::stream::{self, StreamExt};
+use sqlx::{
+    prelude::FromRow,
+    types::{Decimal, Uuid},
+    Executor, PgPool,
+};
+use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tracing::{debug, error, info, instrument, warn};
 
+/// Capacity of the [ShortPositionEvent] broadcast channel. Slow or absent subscribers simply miss
+/// the oldest buffered events rather than applying back-pressure to the harvest loop.
+const EVENT_CHANNEL_CAPACITY: usize = 128;
+
+/// Default upper bound on how many companies [IbexShortFeeder::harvest_batch] fetches
+/// concurrently. Kept modest since [CnmvProvider] already serialises its own requests behind
+/// [CnmvProvider::with_fetch_policy]'s throttle; this bound just lets several companies overlap
+/// their wait time instead of queuing one after another.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Outcome of fetching and recording a single company's positions during a
+/// [IbexShortFeeder::harvest_batch] run.
+#[derive(Debug)]
+pub struct BatchError {
+    /// Ticker of the company whose fetch or DB write failed.
+    pub ticker: String,
+    /// The error raised while fetching from the provider or writing to the data base.
+    pub error: DataProviderError,
+}
+
+/// Summary of a [IbexShortFeeder::harvest_batch] run.
+///
+/// # Description
+///
+/// Unlike [IbexShortFeeder::add_today_data], which aborts the whole run on the first error,
+/// `harvest_batch` keeps going for the rest of the universe and reports every per-company failure
+/// here instead, so that one misbehaving company does not stop the rest of the universe from being
+/// recorded.
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    /// Tickers for which every fetched position was recorded.
+    pub updated_tickers: Vec<String>,
+    /// Per-company errors collected instead of failing the batch.
+    pub errors: Vec<BatchError>,
+}
+
 /// Data provider for short positions against stocks that belong to the Ibex35.
 ///
 /// # Description
@@ -32,8 +78,15 @@ use tracing::{debug, error, info, instrument, warn};
 /// places. These have to be registered in the object's constructor, which keeps a
 /// look-up table that links market regulators with data extractors.
 pub struct IbexShortFeeder<'a> {
-    pub scrapper: Arc<CnmvProvider>,
+    /// Look-up table that links each market [Regulator] with the data provider in charge of it.
+    pub providers: HashMap<Regulator, Arc<dyn ShortPositionProvider>>,
     pub pool: &'a PgPool,
+    /// Broadcasts every [ShortPositionEvent] detected by [IbexShortFeeder::add_today_data]. Use
+    /// [IbexShortFeeder::subscribe] to obtain a receiver.
+    events: broadcast::Sender<ShortPositionEvent>,
+    /// Prometheus metrics updated as the harvest runs. Shared with [admin::AdminServer][crate::admin::AdminServer]'s
+    /// `/metrics` endpoint.
+    pub metrics: Arc<Metrics>,
 }
 
 // Mirror data object of [IbexCompany] to interact with the DB.
@@ -52,8 +105,9 @@ pub struct ShortPositionBd {
     pub id: Option<Uuid>,
     pub owner: Option<String>,
     pub ticker: Option<String>,
-    pub weight: Option<f32>,
+    pub weight: Option<Decimal>,
     pub open_date: Option<NaiveDateTime>,
+    pub mic: Option<String>,
 }
 
 impl TryFrom<&IbexCompanyBd> for IbexCompany {
@@ -126,11 +180,17 @@ impl TryFrom<ShortPositionBd> for ShortPosition {
             None => return Err(DbError::MissingStockInfo("Missing ticker".to_owned())),
         };
 
+        let mic = match value.mic {
+            Some(m) => m,
+            None => return Err(DbError::MissingStockInfo("Missing MIC".to_owned())),
+        };
+
         Ok(ShortPosition {
             owner,
             weight,
             open_date,
             ticker,
+            mic,
         })
     }
 }
@@ -163,20 +223,73 @@ impl TryFrom<&ShortPositionBd> for ShortPosition {
             None => return Err(DbError::MissingStockInfo("Missing ticker".to_owned())),
         };
 
+        let mic = match &value.mic {
+            Some(m) => m.to_owned(),
+            None => return Err(DbError::MissingStockInfo("Missing MIC".to_owned())),
+        };
+
         Ok(ShortPosition {
             owner,
             weight,
             open_date,
             ticker,
+            mic,
         })
     }
 }
 
 impl<'a> IbexShortFeeder<'a> {
     pub fn new(pool: &'a PgPool) -> Self {
+        let mut providers: HashMap<Regulator, Arc<dyn ShortPositionProvider>> = HashMap::new();
+        providers.insert(Regulator::Cnmv, Arc::new(CnmvProvider::new()));
+
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+
         IbexShortFeeder {
-            scrapper: Arc::new(CnmvProvider::new()),
+            providers,
             pool,
+            events,
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+
+    /// Subscribes to the [ShortPositionEvent]s detected by [IbexShortFeeder::add_today_data].
+    ///
+    /// # Description
+    ///
+    /// Every subscriber gets its own independent receiver, fed from the same broadcast channel.
+    /// A subscriber that falls behind loses the oldest events rather than stalling the harvest.
+    pub fn subscribe(&self) -> broadcast::Receiver<ShortPositionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Broadcasts `event` to every subscriber. Having no subscribers is not an error.
+    fn publish(&self, event: ShortPositionEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Checks every registered provider's [ShortPositionProvider::mic] against `registry`, warning
+    /// for any that is not a known ISO 10383 market.
+    ///
+    /// # Description
+    ///
+    /// Every [ShortPosition] a provider produces is tagged with that provider's
+    /// [ShortPositionProvider::mic], on the assumption that it is a standard, joinable market code
+    /// rather than an ad-hoc label. This catches a misconfigured or typo'd MIC at startup instead
+    /// of letting it silently poison the data base. Call it once `registry` has been populated via
+    /// [MicRegistry::refresh] or [MicRegistry::load_from_file].
+    pub fn validate_providers_against(&self, registry: &MicRegistry) {
+        for (regulator, provider) in &self.providers {
+            match registry.lookup(provider.mic()) {
+                Some(mic) => debug!(
+                    "Provider for {regulator} reports positions on {} ({})",
+                    mic.market_name, mic.mic
+                ),
+                None => warn!(
+                    "Provider for {regulator} tags positions with an unrecognised MIC '{}'",
+                    provider.mic()
+                ),
+            }
         }
     }
 
@@ -187,16 +300,36 @@ impl<'a> IbexShortFeeder<'a> {
         // Keep an array with the tickers that get updated.
         let mut updated_tickers = Vec::new();
         debug!("{} companies listed from the IBEX35", companies.len());
+        self.metrics.set_companies_processed(companies.len() as i64);
 
-        // For each company, request to the CNMV's site if there's any open short position.
+        // For each company, request to its regulator's site if there's any open short position.
         for company in companies.iter().filter(|x| x.extra_id().is_some()) {
+            // ISIN presence was just checked above by the filter.
+            let isin = company.extra_id().unwrap();
+            let regulator = Regulator::from_isin(isin);
+
+            let provider = match self.providers.get(&regulator) {
+                Some(provider) => provider,
+                None => {
+                    warn!(
+                        "No data provider registered for regulator {regulator} ({})",
+                        company.ticker()
+                    );
+                    continue;
+                }
+            };
+
             // Build an array of positions coming from the web (new).
-            let new_positions = self
-                .scrapper
-                .short_positions(company)
-                .await
-                .map_err(Box::new)?
-                .positions;
+            let new_positions = match provider.short_positions(company).await {
+                Ok(positions) => {
+                    self.metrics.record_scrape(regulator, true);
+                    positions
+                }
+                Err(e) => {
+                    self.metrics.record_scrape(regulator, false);
+                    return Err(Box::new(e));
+                }
+            };
 
             // And an array of positions coming from the DB (stored).
             let stored_positions = self.active_positions(company.ticker()).await?;
@@ -223,6 +356,12 @@ impl<'a> IbexShortFeeder<'a> {
                         position.ticker, position.owner
                     );
                     self.insert_short_position(&position, None).await?;
+                    self.publish(ShortPositionEvent::Opened {
+                        owner: position.owner,
+                        ticker: position.ticker,
+                        weight: position.weight,
+                        open_date: position.open_date,
+                    });
                 }
             // Second case: All the short positions got reduced below the threshold. We need to wipe all the current
             // active positions.
@@ -235,7 +374,16 @@ impl<'a> IbexShortFeeder<'a> {
 
                 for position in stored_positions.iter() {
                     match &position.id {
-                        Some(id) => self.wipe_short_position(id).await?,
+                        Some(id) => {
+                            self.wipe_short_position(id).await?;
+                            let op = ShortPosition::try_from(position)?;
+                            self.publish(ShortPositionEvent::Closed {
+                                owner: op.owner,
+                                ticker: op.ticker,
+                                weight: op.weight,
+                                open_date: op.open_date,
+                            });
+                        }
                         None => error!("Corrupt data in the DB: {:?}", position),
                     }
                 }
@@ -268,10 +416,11 @@ impl<'a> IbexShortFeeder<'a> {
                     // If found is false, either the position is new, or is an update of an existing one.
                     if !found {
                         // Check if it is an update.
-                        let previous_active_position = match self
+                        let previous_active_position = self
                             .active_position(&new_position.ticker, &new_position.owner)
-                            .await?
-                        {
+                            .await?;
+
+                        let previous_id = match &previous_active_position {
                             Some(p) => {
                                 info!(
                                     "The position owned by {} against {} got updated",
@@ -288,8 +437,23 @@ impl<'a> IbexShortFeeder<'a> {
                             }
                         };
 
-                        self.insert_short_position(new_position, previous_active_position)
-                            .await?;
+                        self.insert_short_position(new_position, previous_id).await?;
+
+                        match previous_active_position.and_then(|p| p.weight) {
+                            Some(old_weight) => self.publish(ShortPositionEvent::Updated {
+                                owner: new_position.owner.clone(),
+                                ticker: new_position.ticker.clone(),
+                                old_weight,
+                                new_weight: new_position.weight,
+                                open_date: new_position.open_date,
+                            }),
+                            None => self.publish(ShortPositionEvent::Opened {
+                                owner: new_position.owner.clone(),
+                                ticker: new_position.ticker.clone(),
+                                weight: new_position.weight,
+                                open_date: new_position.open_date,
+                            }),
+                        }
                     }
                 }
 
@@ -312,6 +476,14 @@ impl<'a> IbexShortFeeder<'a> {
                         self.wipe_short_position(&old_position.id.unwrap()).await?;
                         debug!("Active position {} wiped", old_position.id.unwrap());
                         insert_ticker = true;
+
+                        let op = ShortPosition::try_from(old_position)?;
+                        self.publish(ShortPositionEvent::Closed {
+                            owner: op.owner,
+                            ticker: op.ticker,
+                            weight: op.weight,
+                            open_date: op.open_date,
+                        });
                     }
                 }
 
@@ -324,6 +496,296 @@ impl<'a> IbexShortFeeder<'a> {
         Ok(updated_tickers)
     }
 
+    /// Backfills every publication window missed since `since`.
+    ///
+    /// # Description
+    ///
+    /// Unlike [IbexShortFeeder::add_today_data], which only ever re-fetches today's current
+    /// snapshot, this dispatches each company to the [ShortPositionProvider] in charge of its
+    /// regulator (the same lookup [IbexShortFeeder::add_today_data] uses) and pulls its
+    /// [ShortPositionProvider::historical_positions] since `since`, recording every position found
+    /// that is newer than the active record for the same owner/ticker, so that days the harvest
+    /// was down for are actually reconstructed rather than lost. Used by
+    /// [Scheduler::catch_up][crate::scheduler::Scheduler] before it settles back into the normal
+    /// schedule.
+    #[instrument(name = "Backfill missed publication windows", skip(self))]
+    pub async fn backfill_since(&self, since: DateTime<Utc>) -> Result<(), Box<dyn Error>> {
+        let companies = self.stock_listing().await?;
+
+        for company in companies.iter().filter(|x| x.extra_id().is_some()) {
+            // ISIN presence was just checked above by the filter.
+            let isin = company.extra_id().unwrap();
+            let regulator = Regulator::from_isin(isin);
+
+            let provider = match self.providers.get(&regulator) {
+                Some(provider) => provider,
+                None => {
+                    warn!(
+                        "No data provider registered for regulator {regulator} ({})",
+                        company.ticker()
+                    );
+                    continue;
+                }
+            };
+
+            let mut positions = match provider.historical_positions(company, since).await {
+                Ok(positions) => positions,
+                Err(e) => {
+                    self.metrics.record_scrape(regulator, false);
+                    return Err(Box::new(e));
+                }
+            };
+            self.metrics.record_scrape(regulator, true);
+
+            // Oldest first, so each position is diffed against what the previous iteration just
+            // recorded rather than against a pointer the loop hasn't caught up to yet.
+            positions.sort_by_key(|p| p.open_date);
+
+            for position in positions {
+                // Same lookup IbexShortFeeder::add_today_data uses, so a day that was already
+                // recorded (typically the boundary day `since` itself) is treated as an update of
+                // the existing active row instead of replayed as a brand new one.
+                let existing = self
+                    .active_position(&position.ticker, &position.owner)
+                    .await?;
+
+                match existing {
+                    Some(existing) => {
+                        let existing_position = ShortPosition::try_from(&existing)?;
+
+                        if position.open_date <= existing_position.open_date {
+                            debug!(
+                                "Skipping a backfilled position for {} owned by {} that is not newer than what is already on record",
+                                position.ticker, position.owner
+                            );
+                            continue;
+                        }
+
+                        info!(
+                            "Backfilling an update to the position for {} owned by {}",
+                            position.ticker, position.owner
+                        );
+                        self.insert_short_position(&position, existing.id).await?;
+                    }
+                    None => {
+                        info!(
+                            "Backfilling a missed position for {} owned by {}",
+                            position.ticker, position.owner
+                        );
+                        self.insert_short_position(&position, None).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Concurrently harvests `companies` through each one's regulator-specific provider and
+    /// persists the current alive positions of each one, tolerating per-company failures.
+    ///
+    /// # Description
+    ///
+    /// Unlike [IbexShortFeeder::add_today_data], which walks the universe one company at a time
+    /// and bails out on the first error, this drives `self.providers` over a [stream::iter]
+    /// bounded by `concurrency` via [StreamExt::buffer_unordered]. Each company is dispatched to
+    /// the provider registered for its [Regulator] (the same lookup [IbexShortFeeder::add_today_data]
+    /// uses), so a batch made up of companies from several markets is still harvested correctly
+    /// rather than assuming every company is handled by [CnmvProvider]. A company whose regulator
+    /// has no registered provider is skipped with a `tracing::warn`, the same as in
+    /// [IbexShortFeeder::add_today_data]. [CnmvProvider] still throttles its own requests
+    /// internally, so raising `concurrency` only lets companies overlap their wait time instead of
+    /// sending requests faster than the provider's fetch policy allows.
+    ///
+    /// Every company is looked up as a fresh snapshot (`TimeFrame::Current`). Unlike a true diff,
+    /// no attempt is made to detect positions that disappeared since the last run, but an existing
+    /// active row for the same owner/ticker is still repointed rather than duplicated, so running
+    /// this against an already-populated universe does not pile up extra "active" rows. This is
+    /// meant for seeding or backfilling a company universe, not for the day-to-day refresh that
+    /// [IbexShortFeeder::add_today_data] already handles.
+    ///
+    /// # Returns
+    ///
+    /// A [BatchSummary] listing the tickers whose fetched positions were all recorded, and the
+    /// per-company errors encountered along the way.
+    #[instrument(name = "Harvest a company universe concurrently", skip(self, companies))]
+    pub async fn harvest_batch(&self, companies: &[IbexCompany], concurrency: usize) -> BatchSummary {
+        let fetched: Vec<(&IbexCompany, Option<ShortResult>)> = stream::iter(companies)
+            .map(|company| async move {
+                let isin = match company.extra_id() {
+                    Some(isin) => isin,
+                    None => return (company, None),
+                };
+                let regulator = Regulator::from_isin(isin);
+
+                let provider = match self.providers.get(&regulator) {
+                    Some(provider) => provider,
+                    None => {
+                        warn!(
+                            "No data provider registered for regulator {regulator} ({})",
+                            company.ticker()
+                        );
+                        return (company, None);
+                    }
+                };
+
+                let result = provider.short_positions(company).await;
+                (company, Some(result))
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+
+        let mut summary = BatchSummary::default();
+
+        for (company, result) in fetched {
+            let ticker = company.ticker().to_owned();
+
+            let positions = match result {
+                Some(Ok(positions)) => positions,
+                Some(Err(e)) => {
+                    error!("Failed to fetch positions for {ticker}: {e}");
+                    summary.errors.push(BatchError { ticker, error: e });
+                    continue;
+                }
+                None => continue,
+            };
+
+            if positions.is_empty() {
+                debug!("{ticker} has no open short positions");
+                continue;
+            }
+
+            let mut failed = false;
+            for position in &positions {
+                // Same lookup IbexShortFeeder::add_today_data uses, so harvesting an already
+                // populated universe repoints the existing active row instead of piling up a
+                // second "active" entry for the same owner/ticker.
+                let existing = match self.active_position(&position.ticker, &position.owner).await
+                {
+                    Ok(existing) => existing,
+                    Err(e) => {
+                        error!("Failed to look up the active position for {ticker}: {e}");
+                        summary.errors.push(BatchError {
+                            ticker: ticker.clone(),
+                            error: DataProviderError::from(e),
+                        });
+                        failed = true;
+                        break;
+                    }
+                };
+
+                if let Err(e) = self
+                    .insert_short_position(position, existing.and_then(|p| p.id))
+                    .await
+                {
+                    error!("Failed to record a position for {ticker}: {e}");
+                    summary.errors.push(BatchError {
+                        ticker: ticker.clone(),
+                        error: DataProviderError::from(e),
+                    });
+                    failed = true;
+                    break;
+                }
+            }
+
+            if !failed {
+                summary.updated_tickers.push(ticker);
+            }
+        }
+
+        summary
+    }
+
+    /// Reads the evolution of a ticker's short interest between two dates.
+    ///
+    /// # Description
+    ///
+    /// Unlike [IbexShortFeeder::active_positions], which only ever reflects the current state of
+    /// `ibex35_short_historic`, this reads every dated entry recorded for `ticker` within
+    /// `[from, to]`, regardless of whether the position is still active. This is what lets a
+    /// caller chart how the short interest against a company changed over time.
+    #[instrument(name = "Query short position history for a ticker", skip(self))]
+    pub async fn positions_between(
+        &self,
+        ticker: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ShortPosition>, DbError> {
+        let rows = sqlx::query_as!(
+            ShortPositionBd,
+            r#"
+            SELECT id, owner, weight, open_date, ticker, mic
+            FROM ibex35_short_historic
+            WHERE ticker = $1 AND open_date BETWEEN $2 AND $3
+            ORDER BY open_date
+            "#,
+            ticker,
+            from.naive_utc(),
+            to.naive_utc(),
+        )
+        .fetch_all(self.pool)
+        .await
+        .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+        rows.iter().map(ShortPosition::try_from).collect()
+    }
+
+    /// Batch variant of [IbexShortFeeder::positions_between] that fetches several tickers in a
+    /// single query, returning the matching entries grouped by ticker.
+    #[instrument(name = "Query short position history for several tickers", skip(self))]
+    pub async fn positions_for_tickers(
+        &self,
+        tickers: &[&str],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<HashMap<String, Vec<ShortPosition>>, DbError> {
+        let tickers: Vec<String> = tickers.iter().map(|t| t.to_string()).collect();
+
+        let rows = sqlx::query_as!(
+            ShortPositionBd,
+            r#"
+            SELECT id, owner, weight, open_date, ticker, mic
+            FROM ibex35_short_historic
+            WHERE ticker = ANY($1) AND open_date BETWEEN $2 AND $3
+            ORDER BY ticker, open_date
+            "#,
+            &tickers,
+            from.naive_utc(),
+            to.naive_utc(),
+        )
+        .fetch_all(self.pool)
+        .await
+        .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+        let mut series: HashMap<String, Vec<ShortPosition>> = HashMap::new();
+        for row in rows.iter() {
+            let position = ShortPosition::try_from(row)?;
+            series.entry(position.ticker.clone()).or_default().push(position);
+        }
+
+        Ok(series)
+    }
+
+    /// Reads the most recent `open_date` recorded across every company, or `None` if the record
+    /// is empty. Used by [crate::scheduler::Scheduler] to detect missed publication windows.
+    #[instrument(name = "Get the latest recorded open date", skip(self))]
+    pub async fn latest_open_date(&self) -> Result<Option<DateTime<Utc>>, DbError> {
+        struct LatestOpenDate {
+            open_date: Option<NaiveDateTime>,
+        }
+
+        let row = sqlx::query_as!(
+            LatestOpenDate,
+            "SELECT MAX(open_date) as open_date FROM ibex35_short_historic",
+        )
+        .fetch_one(self.pool)
+        .await
+        .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+        Ok(row.open_date.map(|d| Utc.from_utc_datetime(&d)))
+    }
+
     #[instrument(name = "List the companies of the IBEX35", skip(self))]
     async fn stock_listing(&self) -> Result<Vec<IbexCompany>, DbError> {
         let companies = sqlx::query_as!(IbexCompanyBd, "SELECT * FROM ibex35_listing",)
@@ -348,7 +810,7 @@ impl<'a> IbexShortFeeder<'a> {
         let position = sqlx::query_as!(
             ShortPositionBd,
             r#"
-            SELECT alive_positions.id, owner, weight, open_date, ticker
+            SELECT alive_positions.id, owner, weight, open_date, ticker, mic
             FROM alive_positions INNER JOIN ibex35_short_historic on alive_positions.id = ibex35_short_historic.id
             WHERE ibex35_short_historic.ticker = $1 AND ibex35_short_historic.owner = $2
             "#,
@@ -367,7 +829,7 @@ impl<'a> IbexShortFeeder<'a> {
         let positions = sqlx::query_as!(
             ShortPositionBd,
             r#"
-            SELECT alive_positions.id, owner, weight, open_date, ticker
+            SELECT alive_positions.id, owner, weight, open_date, ticker, mic
             FROM alive_positions INNER JOIN ibex35_short_historic on alive_positions.id = ibex35_short_historic.id
             WHERE ibex35_short_historic.ticker = $1
             "#,
@@ -420,13 +882,14 @@ impl<'a> IbexShortFeeder<'a> {
 
         transaction
             .execute(sqlx::query!(
-                r#"INSERT INTO ibex35_short_historic (id, owner, weight, open_date, ticker)
-                VALUES ($1, $2, $3, $4, $5)"#,
+                r#"INSERT INTO ibex35_short_historic (id, owner, weight, open_date, ticker, mic)
+                VALUES ($1, $2, $3, $4, $5, $6)"#,
                 uuid,
                 position.owner.as_str(),
                 position.weight,
                 position.open_date.naive_utc(),
                 position.ticker,
+                position.mic,
             ))
             .await
             .map_err(|e| DbError::Unknown(e.to_string()))?;
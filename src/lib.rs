@@ -40,12 +40,20 @@
 //! The modules within [feeders] are meant to call modules that produce data and push the new data to the private
 //! data base.
 
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use finance_api::Company;
 
+mod net {
+    mod retry;
+    pub(crate) use retry::{backoff, retry_after};
+}
+
 pub mod feeders {
     mod ibex_short_provider;
-    pub use ibex_short_provider::IbexShortFeeder;
+    pub use ibex_short_provider::{
+        BatchError, BatchSummary, IbexShortFeeder, ShortPositionBd, DEFAULT_BATCH_CONCURRENCY,
+    };
 }
 
 pub mod web_scrappers {
@@ -53,11 +61,38 @@ pub mod web_scrappers {
     pub use cnmv_scrapper::CnmvProvider;
 }
 
+pub mod admin {
+    mod metrics;
+    mod server;
+
+    pub use metrics::Metrics;
+    pub use server::AdminServer;
+}
+
+pub mod notify {
+    mod webhook;
+    pub use webhook::WebhookDispatcher;
+}
+
+pub mod scheduler {
+    mod runner;
+    mod schedule;
+
+    pub use runner::Scheduler;
+    pub use schedule::Schedule;
+}
+
 pub mod domain {
     mod errors;
+    mod event;
+    mod reference;
+    mod regulator;
     mod short_position;
 
-    pub use errors::{CnmvError, DataProviderError, DbError};
+    pub use errors::{CnmvError, DataProviderError, DbError, ReferenceError, WebhookError};
+    pub use event::ShortPositionEvent;
+    pub use reference::{Mic, MicRegistry};
+    pub use regulator::Regulator;
     pub use short_position::{AliveShortPositions, ShortPosition, ShortResponse, ShortResult};
 }
 
@@ -67,7 +102,8 @@ pub enum TimeFrame {
 }
 
 pub(crate) use domain::{
-    AliveShortPositions, CnmvError, DbError, ShortPosition, ShortResponse, ShortResult,
+    AliveShortPositions, CnmvError, DataProviderError, DbError, Regulator, ShortPosition,
+    ShortPositionEvent, ShortResponse, ShortResult, WebhookError,
 };
 
 /// Trait ShortDataProvider
@@ -92,6 +128,7 @@ pub(crate) use domain::{
 /// position holder eventually will need to buy an equal amount of shares to the
 /// size of the short position in order to close it; and historical positions, i.e.
 /// positions that where opened and closed in the past.
+#[async_trait]
 pub trait ShortDataProvider {
     /// Method to check if a stock has/had short positions.
     ///
@@ -108,9 +145,36 @@ pub trait ShortDataProvider {
     /// positions at the time specified by `time_frame`, an empty array is returned.
     ///
     /// When `Err`, an error of type [DataProviderError] is returned.
-    fn get_positions(&self, stock: &impl Company, time_frame: TimeFrame) -> ShortResult;
+    async fn get_positions(&self, stock: &(impl Company + Sync), time_frame: TimeFrame) -> ShortResult;
 }
 
+#[async_trait]
 pub trait ShortDataExtractor {
-    fn get_positions(&self, stock: &impl Company, time_frame: TimeFrame) -> ShortResult;
+    async fn get_positions(&self, stock: &(impl Company + Sync), time_frame: TimeFrame) -> ShortResult;
+}
+
+/// Trait ShortPositionProvider
+///
+/// # Description
+///
+/// Generalised, object-safe counterpart of [ShortDataProvider]. Rather than being implemented
+/// against a concrete stock type, it is meant to be boxed as a trait object so that
+/// [feeders::IbexShortFeeder] can keep one provider per [domain::Regulator] and dispatch each
+/// company to the provider in charge of the market it is registered in, instead of assuming
+/// every company is handled the same way.
+#[async_trait]
+pub trait ShortPositionProvider: Send + Sync {
+    /// Retrieves the short positions currently open against `company`.
+    async fn short_positions(&self, company: &dyn Company) -> ShortResult;
+
+    /// Retrieves every short position notified against `company` at or after `since`, whether
+    /// still active or already closed. This is what lets
+    /// [Scheduler::catch_up][crate::scheduler::Scheduler] actually reconstruct the missed
+    /// publication window(s) instead of only ever refreshing today's current snapshot.
+    async fn historical_positions(&self, company: &dyn Company, since: DateTime<Utc>) -> ShortResult;
+
+    /// The ISO 10383 Market Identifier Code of the exchange this provider reports on, tagged onto
+    /// every [ShortPosition] it returns so that records from multiple regulators can be joined on
+    /// a standard market code rather than an ad-hoc, provider-specific notion of "exchange".
+    fn mic(&self) -> &str;
 }
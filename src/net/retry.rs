@@ -0,0 +1,45 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Shared exponential-backoff-with-jitter and `Retry-After` helpers, used by every component that
+//! retries a flaky HTTP endpoint ([CnmvProvider][crate::web_scrappers::CnmvProvider] against the
+//! CNMV, [WebhookDispatcher][crate::notify::WebhookDispatcher] against a subscriber's endpoint),
+//! so the retry policy does not drift between them as one gets tuned independently of the other.
+
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff with jitter for the given retry attempt, starting from `base`.
+pub(crate) fn backoff(base: Duration, attempt: u32) -> Duration {
+    let exp = base * 2u32.saturating_pow(attempt);
+    let jitter_ms = rand::thread_rng().gen_range(0..100);
+    exp + Duration::from_millis(jitter_ms)
+}
+
+/// Parses the `Retry-After` header of a response, when present, as a number of seconds.
+pub(crate) fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_exponentially_and_adds_jitter() {
+        let base = Duration::from_millis(500);
+
+        let first = backoff(base, 0);
+        let second = backoff(base, 1);
+
+        assert!(first >= base && first < base + Duration::from_millis(100));
+        assert!(second >= base * 2 && second < base * 2 + Duration::from_millis(100));
+    }
+}
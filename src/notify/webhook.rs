@@ -0,0 +1,114 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module that delivers [ShortPositionEvent]s to an external HTTP endpoint.
+
+use crate::net;
+use crate::{ShortPositionEvent, WebhookError};
+use reqwest::Client;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{error, instrument, warn};
+
+/// Dispatches [ShortPositionEvent]s as JSON payloads to a configured webhook URL.
+///
+/// # Description
+///
+/// This `struct` consumes the broadcast channel exposed by
+/// [IbexShortFeeder::subscribe][crate::feeders::IbexShortFeeder::subscribe] and POSTs every
+/// event it receives to `url`, so external systems (alerting, dashboards) get notified the
+/// moment a short position opens, changes weight, or closes. Delivery retries with exponential
+/// backoff and jitter on network errors and `429`/`5xx` responses, honouring a `Retry-After`
+/// header when the server sends one. An event is dropped, with a `tracing::error`, once the
+/// configured retry budget is exhausted, rather than blocking the dispatcher forever.
+pub struct WebhookDispatcher {
+    url: String,
+    client: Client,
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+impl WebhookDispatcher {
+    /// Class constructor.
+    pub fn new(url: impl Into<String>) -> Self {
+        WebhookDispatcher {
+            url: url.into(),
+            client: Client::new(),
+            max_retries: 5,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+
+    /// Overrides the default retry budget and base backoff delay.
+    pub fn with_retry_policy(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Consumes `events`, POSTing every received [ShortPositionEvent] until the channel closes.
+    pub fn listen(self, mut events: broadcast::Receiver<ShortPositionEvent>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = self.deliver(&event).await {
+                            error!("Giving up delivering {:?} to the webhook: {e}", event);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Webhook dispatcher lagged behind, {skipped} events were dropped");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Delivers a single event, retrying with backoff until it succeeds or the retry budget runs out.
+    #[instrument(name = "Deliver short position event", skip(self, event))]
+    async fn deliver(&self, event: &ShortPositionEvent) -> Result<(), WebhookError> {
+        let mut attempt = 0;
+
+        loop {
+            let response = self.client.post(&self.url).json(event).send().await;
+
+            match response {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) if resp.status().as_u16() == 429 || resp.status().is_server_error() => {
+                    if attempt >= self.max_retries {
+                        return Err(WebhookError::DeliveryFailed(resp.status().to_string()));
+                    }
+
+                    let wait = net::retry_after(&resp)
+                        .unwrap_or_else(|| net::backoff(self.base_delay, attempt));
+                    warn!(
+                        "Webhook endpoint returned {}, retrying in {:?}",
+                        resp.status(),
+                        wait
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+                Ok(resp) => return Err(WebhookError::DeliveryFailed(resp.status().to_string())),
+                Err(e) => {
+                    if attempt >= self.max_retries {
+                        return Err(WebhookError::DeliveryFailed(e.to_string()));
+                    }
+
+                    let wait = net::backoff(self.base_delay, attempt);
+                    warn!(
+                        "Failed to reach the webhook endpoint ({e}), retrying in {:?}",
+                        wait
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+            }
+
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module with the scheduler that drives [IbexShortFeeder::add_today_data] automatically.
+
+use crate::feeders::IbexShortFeeder;
+use crate::scheduler::Schedule;
+use crate::DbError;
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tracing::{error, info, instrument, warn};
+
+/// Runs the daily short-position harvest automatically, catching up on any missed publication.
+///
+/// # Description
+///
+/// This `struct` owns the data base pool so that it can be moved wholesale into the background
+/// task spawned by [Scheduler::start]. On startup it compares the latest `open_date` recorded in
+/// `ibex35_short_historic` against the most recently expected publication; if the process was
+/// down across one or more publication windows, it runs the harvest immediately to backfill the
+/// missed day(s) before settling into the normal schedule.
+pub struct Scheduler {
+    pool: PgPool,
+}
+
+impl Scheduler {
+    /// Class constructor.
+    pub fn new(pool: PgPool) -> Self {
+        Scheduler { pool }
+    }
+
+    /// Starts the scheduler loop in the background, returning a handle that never completes
+    /// under normal operation.
+    #[instrument(name = "Start the harvest scheduler", skip(self))]
+    pub fn start(self, schedule: Schedule) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let feeder = IbexShortFeeder::new(&self.pool);
+
+            if let Err(e) = Self::catch_up(&feeder, &schedule).await {
+                error!("Catch-up run failed: {e}");
+            }
+
+            loop {
+                let now = Utc::now();
+                let next_run = schedule.next_publication_after(now);
+                let wait = (next_run - now)
+                    .to_std()
+                    .unwrap_or(std::time::Duration::ZERO);
+
+                info!("Next harvest scheduled at {next_run} (in {wait:?})");
+                tokio::time::sleep(wait).await;
+
+                match feeder.add_today_data().await {
+                    Ok(updated) => {
+                        info!(
+                            "Scheduled harvest run finished, {} ticker(s) updated",
+                            updated.len()
+                        )
+                    }
+                    Err(e) => error!("Scheduled harvest run failed: {e}"),
+                }
+            }
+        })
+    }
+
+    /// Backfills the harvest if the last expected publication window was missed.
+    async fn catch_up(feeder: &IbexShortFeeder<'_>, schedule: &Schedule) -> Result<(), DbError> {
+        let expected = schedule.last_publication_before(Utc::now());
+        let latest = feeder.latest_open_date().await?;
+
+        match latest {
+            Some(latest) if latest >= expected => {
+                info!("No publication window was missed, latest recorded date is {latest}");
+            }
+            _ => {
+                let since = latest.unwrap_or(expected);
+                warn!("A publication window was missed (expected {expected}), backfilling since {since}");
+                if let Err(e) = feeder.backfill_since(since).await {
+                    return Err(DbError::Unknown(e.to_string()));
+                }
+                if let Err(e) = feeder.add_today_data().await {
+                    return Err(DbError::Unknown(e.to_string()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
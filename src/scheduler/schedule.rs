@@ -0,0 +1,129 @@
+// Copyright 2025 Felipe Torres González
+//
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Module with the definition of the daily publication schedule used by the [scheduler][crate::scheduler].
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Europe::Madrid;
+
+/// Daily schedule describing when a market regulator is expected to have published short
+/// positions.
+///
+/// # Description
+///
+/// CNMV publishes short positions once per trading day, no later than 15:30 local time (see
+/// [AliveShortPositions][crate::domain::AliveShortPositions]'s docs). This `struct` captures that
+/// publication deadline so that [Scheduler][crate::scheduler::Scheduler] can compute when the
+/// next harvest run is due, and whether any past run was missed.
+#[derive(Debug, Clone, Copy)]
+pub struct Schedule {
+    /// Local (Europe/Madrid) time of day at which the publication window closes.
+    pub publish_at: NaiveTime,
+}
+
+impl Schedule {
+    /// Class constructor.
+    pub fn new(publish_at: NaiveTime) -> Self {
+        Schedule { publish_at }
+    }
+
+    /// CNMV's well-known publication deadline, no later than 15:30 local time.
+    pub fn cnmv_default() -> Self {
+        Schedule::new(NaiveTime::from_hms_opt(15, 30, 0).unwrap())
+    }
+
+    fn is_trading_day(date: chrono::NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    /// The next instant, strictly after `now`, at which a trading day's publication window closes.
+    pub fn next_publication_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let mut date = now.with_timezone(&Madrid).date_naive();
+
+        loop {
+            if let Some(candidate) = Madrid
+                .from_local_datetime(&date.and_time(self.publish_at))
+                .single()
+            {
+                let candidate = candidate.to_utc();
+                if Self::is_trading_day(date) && candidate > now {
+                    return candidate;
+                }
+            }
+
+            date += Duration::days(1);
+        }
+    }
+
+    /// The most recent instant, at or before `now`, at which a trading day's publication window
+    /// closed. Used to detect whether the process was down across one or more publications.
+    pub fn last_publication_before(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let mut date = now.with_timezone(&Madrid).date_naive();
+
+        loop {
+            if let Some(candidate) = Madrid
+                .from_local_datetime(&date.and_time(self.publish_at))
+                .single()
+            {
+                let candidate = candidate.to_utc();
+                if Self::is_trading_day(date) && candidate <= now {
+                    return candidate;
+                }
+            }
+
+            date -= Duration::days(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn next_publication_after_same_trading_day_before_deadline() {
+        let schedule = Schedule::cnmv_default();
+
+        // 2024-01-02 is a Tuesday, well before the 15:30 Madrid (14:30 UTC in winter) deadline.
+        let next = schedule.next_publication_after(at(2024, 1, 2, 10, 0));
+
+        assert_eq!(next, at(2024, 1, 2, 14, 30));
+    }
+
+    #[test]
+    fn next_publication_after_rolls_over_weekend() {
+        let schedule = Schedule::cnmv_default();
+
+        // 2024-01-05 is a Friday; the publication window has already closed by the evening, so the
+        // next one must skip Saturday and Sunday and land on Monday.
+        let next = schedule.next_publication_after(at(2024, 1, 5, 20, 0));
+
+        assert_eq!(next, at(2024, 1, 8, 14, 30));
+    }
+
+    #[test]
+    fn last_publication_before_same_trading_day_after_deadline() {
+        let schedule = Schedule::cnmv_default();
+
+        let last = schedule.last_publication_before(at(2024, 1, 2, 20, 0));
+
+        assert_eq!(last, at(2024, 1, 2, 14, 30));
+    }
+
+    #[test]
+    fn last_publication_before_rolls_back_over_weekend() {
+        let schedule = Schedule::cnmv_default();
+
+        // 2024-01-06 is a Saturday, a non-trading day, so the last publication must be Friday's.
+        let last = schedule.last_publication_before(at(2024, 1, 6, 10, 0));
+
+        assert_eq!(last, at(2024, 1, 5, 14, 30));
+    }
+}
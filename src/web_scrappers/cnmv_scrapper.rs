@@ -9,14 +9,33 @@
 //! Module that includes code related to the extraction of data from the web page
 //! of the Spanish _Comisión Nacional de Mercado de Valores (CNMV)_.
 
-use crate::{AliveShortPositions, CnmvError, ShortPosition, ShortResponse};
-use chrono::{offset::LocalResult, NaiveDate, TimeZone, Utc};
+use crate::net;
+use crate::{AliveShortPositions, CnmvError, DataProviderError, ShortPosition, ShortResponse};
+use crate::{ShortDataProvider, ShortPositionProvider, ShortResult, TimeFrame};
+use async_trait::async_trait;
+use chrono::{offset::LocalResult, DateTime, NaiveDate, TimeZone, Utc};
 use chrono_tz::Europe::Madrid;
 use finance_api::Company;
-use finance_ibex::IbexCompany;
-use reqwest;
+use reqwest::Client;
+use rust_decimal::Decimal;
 use scraper::{Html, Selector};
-use tracing::{error, instrument, trace};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{error, instrument, trace, warn};
+
+/// User-Agent sent with every request, identifying this library to the CNMV.
+const DEFAULT_USER_AGENT: &str = concat!("data_harvest/", env!("CARGO_PKG_VERSION"));
+/// Per-request timeout applied to the underlying HTTP client.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Minimum delay enforced between consecutive requests to the CNMV.
+const DEFAULT_MIN_DELAY: Duration = Duration::from_millis(500);
+/// Maximum number of retries attempted on a timeout or a `429`/`5xx` response.
+const DEFAULT_MAX_RETRIES: u32 = 5;
+/// Base delay used to compute the exponential backoff between retries.
+const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(500);
+/// MIC of the Bolsa de Madrid, where the CNMV discloses short position notifications.
+const DEFAULT_MIC: &str = "XMAD";
 
 /// Handler to extract data from the CNMV web page.
 ///
@@ -38,6 +57,20 @@ pub struct CnmvProvider {
     base_url: String,
     /// Path extension for the _PosicionesCortas_ endpoint.
     short_ext: String,
+    /// Path extension for the historical _PosicionesCortas_ endpoint.
+    historical_short_ext: String,
+    /// Persistent HTTP client, reused across requests so its connection pool stays warm.
+    client: Client,
+    /// Minimum delay enforced between consecutive requests to the CNMV.
+    min_delay: Duration,
+    /// Maximum number of retries attempted on a timeout or a `429`/`5xx` response.
+    max_retries: u32,
+    /// Base delay used to compute the exponential backoff between retries.
+    base_backoff: Duration,
+    /// Instant of the last request sent, used to throttle to [CnmvProvider::min_delay].
+    last_request: Mutex<Option<Instant>>,
+    /// ISO 10383 Market Identifier Code tagged onto every [ShortPosition] this provider returns.
+    mic: String,
 }
 
 /// `enum` to handle what endpoints of the CNMV's API are supported by this module.
@@ -46,6 +79,9 @@ enum EndpointSel {
     /// EP -> `Consultas a registros oficiales>Entidades emisoras: Información
     /// regulada>Posiciones cortas>Notificaciones de posiciones cortas`
     ShortEP,
+    /// EP -> `Consultas a registros oficiales>Entidades emisoras: Información
+    /// regulada>Posiciones cortas>Notificaciones históricas de posiciones cortas`
+    HistoricalShortEP,
 }
 
 impl Default for CnmvProvider {
@@ -58,12 +94,58 @@ impl Default for CnmvProvider {
 impl CnmvProvider {
     /// Class constructor.
     pub fn new() -> CnmvProvider {
+        let client = Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .timeout(DEFAULT_TIMEOUT)
+            .build()
+            .expect("failed to build the HTTP client");
+
         CnmvProvider {
             base_url: String::from("https://www.cnmv.es"),
             short_ext: String::from("Portal/Consultas/EE/PosicionesCortas.aspx?nif="),
+            historical_short_ext: String::from(
+                "Portal/Consultas/EE/PosicionesCortasHistorico.aspx?nif=",
+            ),
+            client,
+            min_delay: DEFAULT_MIN_DELAY,
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_backoff: DEFAULT_BASE_BACKOFF,
+            last_request: Mutex::new(None),
+            mic: String::from(DEFAULT_MIC),
         }
     }
 
+    /// Overrides the MIC tagged onto the positions returned by this provider. Useful for
+    /// companies whose short positions are mainly disclosed against a market other than the
+    /// default, the Bolsa de Madrid (`XMAD`).
+    pub fn with_mic(mut self, mic: impl Into<String>) -> Self {
+        self.mic = mic.into();
+        self
+    }
+
+    /// Overrides the default User-Agent, request timeout, throttling delay and retry policy.
+    ///
+    /// Lets batch feeders tune how politely this provider talks to the CNMV, e.g. backing off
+    /// further when harvesting the whole Ibex35 in one run.
+    pub fn with_fetch_policy(
+        mut self,
+        user_agent: impl AsRef<str>,
+        timeout: Duration,
+        min_delay: Duration,
+        max_retries: u32,
+        base_backoff: Duration,
+    ) -> Self {
+        self.client = Client::builder()
+            .user_agent(user_agent.as_ref())
+            .timeout(timeout)
+            .build()
+            .expect("failed to build the HTTP client");
+        self.min_delay = min_delay;
+        self.max_retries = max_retries;
+        self.base_backoff = base_backoff;
+        self
+    }
+
     /// Internal method that executes a GET to the CNMV's web page endpoints.
     ///
     /// # Description
@@ -71,6 +153,10 @@ impl CnmvProvider {
     /// This method's implementation is generic, so it shall be used to retrieve data from any supported endpoint of
     /// CNMV's page. See [EndpointSel] for a full list of the supported endpoints.
     ///
+    /// Requests are throttled to [CnmvProvider::min_delay] apart, and a timeout or a `429`/`5xx`
+    /// response is retried with exponential backoff plus jitter, honouring a `Retry-After` header
+    /// when the CNMV sends one, up to [CnmvProvider::max_retries] attempts.
+    ///
     /// # Returns
     ///
     /// When the HTTP GET operation succeeded, the response will contain all the raw data in a String. Thus this
@@ -80,7 +166,7 @@ impl CnmvProvider {
     /// The following errors might happen:
     /// - [CnmvError::MissingIsin] when the given company has no ISIN. This might happen for companies that are listed
     ///   in the Ibex35 but are not registered in Spain.
-    /// - [CnmvError::ExternalError] when any error is returned from the HTTP request.
+    /// - [CnmvError::ExternalError] when the retry budget is exhausted or a non-retryable error is returned.
     #[instrument(
       name = "Collect data from CNMV's page"
       skip(self, stock),
@@ -89,11 +175,12 @@ impl CnmvProvider {
     async fn collect_data(
         &self,
         endpoint: EndpointSel,
-        stock: &IbexCompany,
+        stock: &dyn Company,
     ) -> Result<ShortResponse, CnmvError> {
         // Select the endpoint that shall be used for the requested GET.
         let endpoint = match endpoint {
             EndpointSel::ShortEP => &self.short_ext[..],
+            EndpointSel::HistoricalShortEP => &self.historical_short_ext[..],
         };
 
         // Retrieve the companie's ISIN.
@@ -105,23 +192,66 @@ impl CnmvProvider {
             }
         };
 
-        let resp = reqwest::get(format!("{}/{endpoint}{isin}", self.base_url))
-            .await
-            .map_err(|e| CnmvError::ExternalError(e.to_string()))?;
-
-        if resp.status().as_u16() != 200 {
-            let error_string = resp.status().as_str().to_string();
-            error!("Error found during the request: {error_string}");
-            Err(CnmvError::ExternalError(error_string))
-        } else {
-            let response = ShortResponse::parse(
-                resp.text()
-                    .await
-                    .map_err(|e| CnmvError::InternalError(e.to_string()))?,
-            )?;
-            trace!("Response: {:?}", response);
-            Ok(response)
+        let url = format!("{}/{endpoint}{isin}", self.base_url);
+        let mut attempt = 0;
+
+        loop {
+            self.throttle().await;
+
+            let response = self.client.get(&url).send().await;
+
+            match response {
+                Ok(resp) if resp.status().as_u16() == 200 => {
+                    let response = ShortResponse::parse(
+                        resp.text()
+                            .await
+                            .map_err(|e| CnmvError::InternalError(e.to_string()))?,
+                    )?;
+                    trace!("Response: {:?}", response);
+                    return Ok(response);
+                }
+                Ok(resp) if resp.status().as_u16() == 429 || resp.status().is_server_error() => {
+                    if attempt >= self.max_retries {
+                        let error_string = resp.status().as_str().to_string();
+                        error!("Retry budget exhausted against the CNMV: {error_string}");
+                        return Err(CnmvError::ExternalError(error_string));
+                    }
+
+                    let wait = net::retry_after(&resp)
+                        .unwrap_or_else(|| net::backoff(self.base_backoff, attempt));
+                    warn!("The CNMV returned {}, retrying in {:?}", resp.status(), wait);
+                    tokio::time::sleep(wait).await;
+                }
+                Ok(resp) => {
+                    let error_string = resp.status().as_str().to_string();
+                    error!("Error found during the request: {error_string}");
+                    return Err(CnmvError::ExternalError(error_string));
+                }
+                Err(e) if e.is_timeout() && attempt < self.max_retries => {
+                    let wait = net::backoff(self.base_backoff, attempt);
+                    warn!("Request to the CNMV timed out, retrying in {:?}", wait);
+                    tokio::time::sleep(wait).await;
+                }
+                Err(e) => return Err(CnmvError::ExternalError(e.to_string())),
+            }
+
+            attempt += 1;
+        }
+    }
+
+    /// Sleeps, if needed, so that at least [CnmvProvider::min_delay] elapses since the previous
+    /// request sent by this provider.
+    async fn throttle(&self) {
+        let mut last_request = self.last_request.lock().await;
+
+        if let Some(last) = *last_request {
+            let elapsed = last.elapsed();
+            if elapsed < self.min_delay {
+                tokio::time::sleep(self.min_delay - elapsed).await;
+            }
         }
+
+        *last_request = Some(Instant::now());
     }
 
     /// Method that parses the short positions from CNMV's web site.
@@ -150,78 +280,220 @@ impl CnmvProvider {
       )]
     pub async fn short_positions(
         &self,
-        stock: &IbexCompany,
+        stock: &dyn Company,
     ) -> Result<AliveShortPositions, CnmvError> {
         let raw_data = self.collect_data(EndpointSel::ShortEP, stock).await?;
+        let (positions, skipped_rows) =
+            Self::parse_positions_table(raw_data.as_ref(), stock.ticker(), &self.mic);
+
+        let mut total = Decimal::ZERO;
+        positions
+            .iter()
+            .for_each(|position| total += position.weight);
+        let date = Utc::now();
+
+        Ok(AliveShortPositions {
+            total,
+            positions,
+            date,
+            skipped_rows,
+        })
+    }
 
-        let document = Html::parse_document(raw_data.as_ref());
+    /// Method that parses the historical short positions from CNMV's web site.
+    ///
+    /// # Description
+    ///
+    /// This method parses CNMV's historical notifications page, which lists every short
+    /// position notified against a company, whether still active or already closed, unlike
+    /// [CnmvProvider::short_positions], which only reflects the current, active snapshot.
+    ///
+    /// ## Arguments
+    ///
+    /// - _stock_: An instance of an [IbexCompany].
+    /// - _since_: Only positions opened at or after this date are returned.
+    ///
+    /// ## Returns
+    ///
+    /// Every [ShortPosition] whose open date falls between `since` and now, letting callers
+    /// reconstruct the full short-interest history of a company for backtesting and
+    /// quantitative analysis.
+    #[instrument(
+        name = "Parse historical data from CNMV's page"
+        skip(self, stock),
+        fields(stock.name=stock.name(), stock.isin=stock.extra_id())
+      )]
+    pub async fn historical_positions(
+        &self,
+        stock: &dyn Company,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<ShortPosition>, CnmvError> {
+        let raw_data = self
+            .collect_data(EndpointSel::HistoricalShortEP, stock)
+            .await?;
+        let (positions, skipped_rows) =
+            Self::parse_positions_table(raw_data.as_ref(), stock.ticker(), &self.mic);
+        if skipped_rows > 0 {
+            warn!(
+                "Skipped {skipped_rows} malformed row(s) while parsing historical positions for {}",
+                stock.ticker()
+            );
+        }
+
+        Ok(positions
+            .into_iter()
+            .filter(|position| position.open_date >= since && position.open_date <= Utc::now())
+            .collect())
+    }
+
+    /// Parses a CNMV notifications table (shared by the current and historical endpoints, which
+    /// render the same markup) into a collection of [ShortPosition].
+    ///
+    /// # Description
+    ///
+    /// The CNMV's markup is not versioned, so a layout change upstream may leave some rows with a
+    /// missing or unparseable field. Rather than aborting the whole extraction, each row is parsed
+    /// independently by [CnmvProvider::try_parse_row] and a malformed one is simply dropped: it is
+    /// logged and counted, and parsing continues with the remaining rows. The second element of the
+    /// returned tuple is the number of rows that were skipped this way, so callers can tell a
+    /// genuinely position-free result apart from one where parsing silently broke.
+    fn parse_positions_table(html: &str, ticker: &str, mic: &str) -> (Vec<ShortPosition>, usize) {
+        let document = Html::parse_document(html);
         let selector_td = Selector::parse("td").unwrap();
         let selector_tr = Selector::parse("tr").unwrap();
 
         let mut positions = Vec::new();
+        let mut skipped_rows = 0;
 
         for element_tr in document.select(&selector_tr) {
-            let mut owner: String = String::from("dummy");
-            let mut weight: f32 = 0.0;
-            let mut date: String = String::from("nodate");
-            for td in element_tr.select(&selector_td) {
-                if let Some(x) = td.attr("class") {
-                    if x == "Izquierda" {
-                        owner = String::from(td.text().next().unwrap().trim());
-                    }
-                } else if let Some(x) = td.attr("data-th") {
-                    if x == "% sobre el capital" {
-                        weight = td
-                            .text()
-                            .next()
-                            .unwrap()
-                            .replace(',', ".")
-                            .parse::<f32>()
-                            .unwrap();
-                    } else if x == "Fecha de la posición" {
-                        date = String::from(td.text().next().unwrap());
-                    }
+            match Self::try_parse_row(element_tr, &selector_td, ticker, mic) {
+                Ok(Some(position)) => positions.push(position),
+                // Not a data row (e.g. the header row), nothing to report.
+                Ok(None) => {}
+                Err(()) => {
+                    skipped_rows += 1;
+                    warn!("Skipping a malformed row in the CNMV notifications table for {ticker}");
                 }
             }
+        }
+
+        (positions, skipped_rows)
+    }
+
+    /// Parses a single `<tr>` of a CNMV notifications table.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(position))` when the row carries a short position.
+    /// - `Ok(None)` when the row has no owner cell, i.e. it is not a data row (such as the header).
+    /// - `Err(())` when the row has an owner cell but a required field is missing or cannot be
+    ///   parsed, which the caller treats as a malformed row to skip.
+    fn try_parse_row(
+        element_tr: scraper::ElementRef,
+        selector_td: &Selector,
+        ticker: &str,
+        mic: &str,
+    ) -> Result<Option<ShortPosition>, ()> {
+        let mut owner: Option<String> = None;
+        let mut weight_raw: Option<String> = None;
+        let mut date_raw: Option<String> = None;
 
-            if &owner[..] != "dummy" {
-                let date = NaiveDate::parse_from_str(&date, "%d/%m/%Y").map_err(|_| {
-                    CnmvError::InternalError(
-                        "Failed to parse the short position open date.".to_owned(),
-                    )
-                })?;
-
-                let open_date =
-                    match Madrid.from_local_datetime(&date.and_hms_opt(15, 30, 0).unwrap()) {
-                        LocalResult::Single(value) => value.to_utc(),
-                        _ => {
-                            error!("The given naive date does not convert to UTC.");
-                            return Err(CnmvError::InternalError(
-                                "Failed to build a valid date.".to_owned(),
-                            ));
-                        }
-                    };
-
-                positions.push(ShortPosition {
-                    owner,
-                    weight,
-                    open_date,
-                    ticker: stock.ticker().to_owned(),
-                });
+        for td in element_tr.select(selector_td) {
+            if let Some(x) = td.attr("class") {
+                if x == "Izquierda" {
+                    owner = td.text().next().map(|text| text.trim().to_owned());
+                }
+            } else if let Some(x) = td.attr("data-th") {
+                if x == "% sobre el capital" {
+                    weight_raw = td.text().next().map(|text| text.to_owned());
+                } else if x == "Fecha de la posición" {
+                    date_raw = td.text().next().map(|text| text.to_owned());
+                }
             }
         }
 
-        let mut total = 0.0;
-        positions
-            .iter()
-            .for_each(|position| total += position.weight);
-        let date = Utc::now();
+        let owner = match owner {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
 
-        Ok(AliveShortPositions {
-            total,
-            positions,
-            date,
-        })
+        let weight = weight_raw
+            .as_deref()
+            .and_then(|raw| Decimal::from_str(&raw.replace(',', ".")).ok())
+            .ok_or(())?;
+
+        let date = date_raw
+            .as_deref()
+            .and_then(|raw| NaiveDate::parse_from_str(raw, "%d/%m/%Y").ok())
+            .ok_or(())?;
+
+        let open_date = match Madrid.from_local_datetime(&date.and_hms_opt(15, 30, 0).ok_or(())?) {
+            LocalResult::Single(value) => value.to_utc(),
+            _ => {
+                error!("The given naive date does not convert to UTC.");
+                return Err(());
+            }
+        };
+
+        Ok(Some(ShortPosition {
+            owner,
+            weight,
+            open_date,
+            ticker: ticker.to_owned(),
+            mic: mic.to_owned(),
+        }))
+    }
+}
+
+#[async_trait]
+impl ShortPositionProvider for CnmvProvider {
+    async fn short_positions(&self, company: &dyn Company) -> ShortResult {
+        let alive = CnmvProvider::short_positions(self, company)
+            .await
+            .map_err(DataProviderError::from)?;
+
+        if alive.skipped_rows > 0 {
+            warn!(
+                "Skipped {} malformed row(s) while parsing current positions for {}",
+                alive.skipped_rows,
+                company.ticker()
+            );
+        }
+
+        Ok(alive.positions)
+    }
+
+    async fn historical_positions(
+        &self,
+        company: &dyn Company,
+        since: DateTime<Utc>,
+    ) -> ShortResult {
+        CnmvProvider::historical_positions(self, company, since)
+            .await
+            .map_err(DataProviderError::from)
+    }
+
+    fn mic(&self) -> &str {
+        &self.mic
+    }
+}
+
+#[async_trait]
+impl ShortDataProvider for CnmvProvider {
+    /// Dispatches to [CnmvProvider::short_positions] for `TimeFrame::Current`, and to
+    /// [CnmvProvider::historical_positions] for `TimeFrame::Historical`, so that callers driving
+    /// this provider through the generic [ShortDataProvider] trait get the same current/historical
+    /// split as callers using the inherent methods directly.
+    async fn get_positions(&self, stock: &(impl Company + Sync), time_frame: TimeFrame) -> ShortResult {
+        match time_frame {
+            TimeFrame::Current => CnmvProvider::short_positions(self, stock)
+                .await
+                .map(|alive| alive.positions)
+                .map_err(DataProviderError::from),
+            TimeFrame::Historical(since) => CnmvProvider::historical_positions(self, stock, since)
+                .await
+                .map_err(DataProviderError::from),
+        }
     }
 }
 
@@ -331,4 +603,28 @@ mod tests {
                 assert!(short_position.is_err());
             })
     }
+
+    #[rstest]
+    fn parse_positions_table_skips_malformed_rows() {
+        // One well-formed row, and one row missing the weight cell, which is malformed.
+        let html = r#"
+            <table>
+                <tr>
+                    <td class="Izquierda">Fondo Correcto</td>
+                    <td data-th="% sobre el capital">1,23</td>
+                    <td data-th="Fecha de la posición">01/02/2024</td>
+                </tr>
+                <tr>
+                    <td class="Izquierda">Fondo Incorrecto</td>
+                    <td data-th="Fecha de la posición">02/02/2024</td>
+                </tr>
+            </table>
+        "#;
+
+        let (positions, skipped_rows) = CnmvProvider::parse_positions_table(html, "TICK", "XMAD");
+
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].owner, "Fondo Correcto");
+        assert_eq!(skipped_rows, 1);
+    }
 }